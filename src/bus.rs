@@ -0,0 +1,104 @@
+//! Memory access abstraction.
+//!
+//! `CPU` talks to memory only through the [`Bus`] trait, so a consumer can
+//! swap in whatever backing store (or memory-mapped peripherals) it needs
+//! without touching the core. [`RamBus`] is the default: a flat 64K array,
+//! the same shape the core used before this trait existed.
+
+/// A 16-bit address space the CPU can read and write a byte at a time.
+pub trait Bus {
+    fn read(&self, addr: u16) -> u8;
+    fn write(&mut self, addr: u16, val: u8);
+}
+
+type WriteHandler = Box<dyn FnMut(u16, u8)>;
+
+/// Flat 64K RAM, with optional address-range write handlers for
+/// memory-mapped devices.
+pub struct RamBus {
+    data: [u8; 65536],
+    write_handlers: Vec<(u16, u16, WriteHandler)>,
+}
+
+impl RamBus {
+    pub fn new() -> Self {
+        RamBus {
+            data: [0; 65536],
+            write_handlers: Vec::new(),
+        }
+    }
+
+    pub fn initialize(&mut self) {
+        self.data = [0; 65536];
+    }
+
+    /// Forward writes to addresses in `start..=end` to `handler` instead of
+    /// storing them in RAM. Ranges are checked in registration order; the
+    /// first match wins.
+    pub fn register_write_handler(
+        &mut self,
+        start: u16,
+        end: u16,
+        handler: impl FnMut(u16, u8) + 'static,
+    ) {
+        self.write_handlers.push((start, end, Box::new(handler)));
+    }
+}
+
+impl Default for RamBus {
+    fn default() -> Self {
+        RamBus::new()
+    }
+}
+
+impl Bus for RamBus {
+    fn read(&self, addr: u16) -> u8 {
+        self.data[addr as usize]
+    }
+
+    fn write(&mut self, addr: u16, val: u8) {
+        for (start, end, handler) in self.write_handlers.iter_mut() {
+            if addr >= *start && addr <= *end {
+                handler(addr, val);
+                return;
+            }
+        }
+
+        self.data[addr as usize] = val;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn write_in_handler_range_is_forwarded_instead_of_stored() {
+        let mut bus = RamBus::new();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+
+        let seen_handle = Rc::clone(&seen);
+        bus.register_write_handler(0x4000, 0x4000, move |addr, val| {
+            seen_handle.borrow_mut().push((addr, val));
+        });
+
+        bus.write(0x4000, 0xAB);
+
+        assert_eq!(*seen.borrow(), vec![(0x4000, 0xAB)]);
+        assert_eq!(bus.read(0x4000), 0); // never written to RAM
+    }
+
+    #[test]
+    fn write_outside_handler_range_falls_through_to_ram() {
+        let mut bus = RamBus::new();
+        bus.register_write_handler(0x4000, 0x4000, |_, _| {
+            panic!("handler should not fire outside its range");
+        });
+
+        bus.write(0x5000, 0xCD);
+
+        assert_eq!(bus.read(0x5000), 0xCD);
+    }
+}
@@ -0,0 +1,763 @@
+use std::io::Write;
+use std::marker::PhantomData;
+
+use modular_bitfield::prelude::*;
+
+use crate::bus::Bus;
+use crate::disasm::disassemble;
+use crate::instr::{get_instruction, operand_len, AddrMode, Op};
+use crate::variant::{Nmos6502, Variant};
+
+#[derive(Clone, Copy)]
+#[bitfield(bits = 8)]
+pub struct PS {
+    pub c: bool, //Carry Flag
+    pub z: bool, //Zero Flag
+    pub i: bool, //Interupt Disable
+    pub d: bool, //Decimal Mode
+    pub b: bool, //Break Command
+    pub u: B1,   //Unused
+    pub v: bool, //Overflow Flag
+    pub n: bool, //Negative Flag
+}
+
+/// The stack lives at 0x0100-0x01FF and grows downward: `sp` is the offset
+/// of the next free byte within that page.
+const STACK_PAGE: u16 = 0x0100;
+
+const RESET_VECTOR: u16 = 0xFFFC;
+const NMI_VECTOR: u16 = 0xFFFA;
+const IRQ_VECTOR: u16 = 0xFFFE;
+
+/// Cycles charged for servicing a pending `irq()`/`nmi()` (two dummy reads,
+/// push PC and status, fetch the vector). `BRK`'s cost comes from its own
+/// table row instead, since it is a regular fetched instruction.
+const INTERRUPT_CYCLES: u32 = 7;
+
+/// Binary-coded-decimal addition, nibble by nibble, with decimal carry
+/// adjustment: if a nibble's sum exceeds 9, add 6 and carry into the next
+/// nibble. Returns the BCD result and the decimal carry-out.
+#[cfg(feature = "decimal_mode")]
+fn bcd_add(a: u8, b: u8, carry_in: u8) -> (u8, bool) {
+    let mut lo = (a & 0x0F) + (b & 0x0F) + carry_in;
+    let mut carry = 0u8;
+    if lo > 9 {
+        lo += 6;
+        carry = 1;
+    }
+
+    let mut hi = (a >> 4) + (b >> 4) + carry;
+    let mut carry_out = false;
+    if hi > 9 {
+        hi += 6;
+        carry_out = true;
+    }
+
+    (((hi & 0x0F) << 4) | (lo & 0x0F), carry_out)
+}
+
+/// Binary-coded-decimal subtraction, nibble by nibble. `carry_in` follows
+/// 6502 convention (1 == no borrow). Returns the BCD result and whether a
+/// borrow did *not* occur (the value the C flag takes).
+#[cfg(feature = "decimal_mode")]
+fn bcd_sub(a: u8, b: u8, carry_in: u8) -> (u8, bool) {
+    let borrow_in: i16 = if carry_in != 0 { 0 } else { 1 };
+
+    let mut lo = (a & 0x0F) as i16 - (b & 0x0F) as i16 - borrow_in;
+    let mut borrow = 0i16;
+    if lo < 0 {
+        lo += 10;
+        borrow = 1;
+    }
+
+    let mut hi = (a >> 4) as i16 - (b >> 4) as i16 - borrow;
+    let no_borrow = if hi < 0 {
+        hi += 10;
+        false
+    } else {
+        true
+    };
+
+    (((hi as u8 & 0x0F) << 4) | (lo as u8 & 0x0F), no_borrow)
+}
+
+/// Defaults to [`Nmos6502`]; pick a different chip with `CPU::<Cmos65C02>::new()`.
+#[allow(clippy::upper_case_acronyms)]
+pub struct CPU<V: Variant = Nmos6502> {
+    pub pc: u16, //Program Counter
+    pub sp: u8,  //Stack Poniter
+    pub a: u8,   //Accumulator
+    pub x: u8,   //Register X
+    pub y: u8,   //Register Y
+    pub ps: PS,  //Processor Status
+    pub nmi_pending: bool,
+    pub irq_pending: bool,
+    /// Running cycle count, surfaced in trace output.
+    pub total_cycles: u64,
+    /// When set (via `set_trace_writer`), a line is written before every
+    /// instruction is executed.
+    pub trace: bool,
+    trace_writer: Option<Box<dyn Write>>,
+    _variant: PhantomData<V>,
+}
+
+impl<V: Variant> CPU<V> {
+    pub fn new() -> Self {
+        CPU {
+            pc: 0,
+            sp: 0,
+            a: 0,
+            x: 0,
+            y: 0,
+            ps: PS::new(),
+            nmi_pending: false,
+            irq_pending: false,
+            total_cycles: 0,
+            trace: false,
+            trace_writer: None,
+            _variant: PhantomData,
+        }
+    }
+
+    /// Enable trace mode, writing one formatted line per instruction to
+    /// `writer` (PC, raw opcode bytes, disassembly, register/flag snapshot,
+    /// SP, and running cycle count). Pairs with known-good reference traces
+    /// from 6502 test ROMs for line-by-line diffing.
+    pub fn set_trace_writer(&mut self, writer: Box<dyn Write>) {
+        self.trace = true;
+        self.trace_writer = Some(writer);
+    }
+}
+
+impl<V: Variant> Default for CPU<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V: Variant> CPU<V> {
+    /// Reset loads `pc` from the 16-bit reset vector at 0xFFFC/0xFFFD, the
+    /// way real hardware does, rather than jumping straight to a fixed
+    /// address. Memory is left untouched so a caller can load the reset
+    /// vector and program image before calling `reset`.
+    pub fn reset<M: Bus>(&mut self, mem: &M) {
+        self.sp = 0xFE;
+        self.ps = PS::new();
+        self.a = 0;
+        self.x = 0;
+        self.y = 0;
+        self.nmi_pending = false;
+        self.irq_pending = false;
+        self.total_cycles = 0;
+
+        self.pc = CPU::<V>::read_word(mem, RESET_VECTOR);
+    }
+
+    /// Request a maskable interrupt. The request stays pending until the I
+    /// flag is clear and the next instruction boundary services it.
+    pub fn irq(&mut self) {
+        self.irq_pending = true;
+    }
+
+    /// Request a non-maskable interrupt. NMI is edge-triggered: it fires
+    /// once per call to this method, regardless of the I flag.
+    pub fn nmi(&mut self) {
+        self.nmi_pending = true;
+    }
+
+    fn push_byte<M: Bus>(&mut self, memory: &mut M, value: u8) {
+        memory.write(STACK_PAGE + self.sp as u16, value);
+        self.sp = self.sp.wrapping_sub(1);
+    }
+
+    fn pull_byte<M: Bus>(&mut self, memory: &M) -> u8 {
+        self.sp = self.sp.wrapping_add(1);
+        memory.read(STACK_PAGE + self.sp as u16)
+    }
+
+    fn push_word<M: Bus>(&mut self, memory: &mut M, value: u16) {
+        self.push_byte(memory, (value >> 8) as u8);
+        self.push_byte(memory, value as u8);
+    }
+
+    fn pull_word<M: Bus>(&mut self, memory: &M) -> u16 {
+        let lo = self.pull_byte(memory) as u16;
+        let hi = self.pull_byte(memory) as u16;
+        lo | (hi << 8)
+    }
+
+    /// Push PC and status (with the break flag set as requested) and vector
+    /// through `vector`. Shared by `BRK`, `irq()` and `nmi()` handling.
+    fn service_interrupt<M: Bus>(&mut self, memory: &mut M, vector: u16, brk: bool) {
+        self.push_word(memory, self.pc);
+
+        let mut status = self.ps;
+        status.set_b(brk);
+        status.set_u(1);
+        self.push_byte(memory, status.into_bytes()[0]);
+
+        self.ps.set_i(true);
+        if brk && V::clears_decimal_on_brk() {
+            self.ps.set_d(false);
+        }
+        self.pc = CPU::<V>::read_word(memory, vector);
+    }
+
+    pub fn fetch_byte<M: Bus>(&mut self, memory: &M) -> u8 {
+        let data = memory.read(self.pc);
+        self.pc += 1;
+        data
+    }
+
+    pub fn fetch_word<M: Bus>(&mut self, memory: &M) -> u16 {
+        // 6502 is little endian
+        let mut data = memory.read(self.pc) as u16;
+        self.pc += 1;
+
+        data |= (memory.read(self.pc) as u16) << 8;
+        self.pc += 1;
+
+        data
+    }
+
+    pub fn read_byte<M: Bus>(memory: &M, addr: u16) -> u8 {
+        memory.read(addr)
+    }
+
+    pub fn read_word<M: Bus>(memory: &M, addr: u16) -> u16 {
+        let low_byte = memory.read(addr);
+        let hi_byte = memory.read(addr + 1);
+
+        (low_byte as u16) | ((hi_byte as u16) << 8)
+    }
+
+    pub fn addr_absolute<M: Bus>(&mut self, memory: &M) -> u16 {
+        self.fetch_word(memory)
+    }
+
+    pub fn set_zero_and_negative_flags(&mut self, register: u8) {
+        self.ps.set_z(register == 0);
+        self.ps.set_n((register & 0b1000_0000) > 0);
+    }
+
+    /// `ADC`: add `value` and the carry flag into A. N, V and Z are always
+    /// derived from the binary sum -- a well-documented NMOS quirk that
+    /// also holds in decimal mode, per 6502.org's decimal-mode notes and
+    /// Klaus Dormann's decimal test ROM (e.g. $99 + $01 wraps to BCD $00
+    /// but leaves Z clear, since the binary intermediate $9A is nonzero).
+    /// Only C and the stored result use the BCD-corrected sum when the D
+    /// flag is set and the `decimal_mode` feature is enabled.
+    fn adc(&mut self, value: u8) {
+        let a = self.a;
+        let carry_in = self.ps.c() as u16;
+        let bin_sum = a as u16 + value as u16 + carry_in;
+        let bin_result = bin_sum as u8;
+
+        let overflow = ((a ^ bin_result) & (value ^ bin_result) & 0x80) != 0;
+        let negative = (bin_result & 0x80) != 0;
+
+        #[allow(unused_mut)]
+        let mut result = bin_result;
+        #[allow(unused_mut)]
+        let mut carry_out = bin_sum > 0xFF;
+
+        #[cfg(feature = "decimal_mode")]
+        if self.ps.d() {
+            let (bcd_result, bcd_carry) = bcd_add(a, value, carry_in as u8);
+            result = bcd_result;
+            carry_out = bcd_carry;
+        }
+
+        self.ps.set_c(carry_out);
+        self.ps.set_v(overflow);
+        self.ps.set_z(bin_result == 0);
+        self.ps.set_n(negative);
+        self.a = result;
+    }
+
+    /// `SBC`: subtract `value` and the borrow (inverted carry) from A. Same
+    /// binary-intermediate quirk for N/V/Z as `adc`.
+    fn sbc(&mut self, value: u8) {
+        let a = self.a;
+        let carry_in = self.ps.c() as u8; // 1 == no borrow
+        let inv = !value;
+        let bin_sum = a as u16 + inv as u16 + carry_in as u16;
+        let bin_result = bin_sum as u8;
+
+        let overflow = ((a ^ bin_result) & (inv ^ bin_result) & 0x80) != 0;
+        let negative = (bin_result & 0x80) != 0;
+
+        #[allow(unused_mut)]
+        let mut result = bin_result;
+        #[allow(unused_mut)]
+        let mut no_borrow = bin_sum > 0xFF;
+
+        #[cfg(feature = "decimal_mode")]
+        if self.ps.d() {
+            let (bcd_result, bcd_no_borrow) = bcd_sub(a, value, carry_in);
+            result = bcd_result;
+            no_borrow = bcd_no_borrow;
+        }
+
+        self.ps.set_c(no_borrow);
+        self.ps.set_v(overflow);
+        self.ps.set_z(bin_result == 0);
+        self.ps.set_n(negative);
+        self.a = result;
+    }
+
+    /// Resolve the effective address for `mode`, fetching any operand bytes
+    /// the mode requires. Returns the address together with whether
+    /// resolving it crossed a page boundary, so callers can apply the
+    /// uniform page-cross cycle penalty where the addressing mode allows
+    /// one (the exact penalty is variant-dependent; see
+    /// `Variant::indexed_cross_penalty`).
+    fn resolve_addr<M: Bus>(&mut self, mode: AddrMode, memory: &M) -> (u16, bool) {
+        match mode {
+            AddrMode::Imm => {
+                let addr = self.pc;
+                self.pc += 1;
+                (addr, false)
+            }
+            AddrMode::Zp0 => {
+                let addr = self.fetch_byte(memory) as u16;
+                (addr, false)
+            }
+            AddrMode::Zpx => {
+                let addr = self.fetch_byte(memory).wrapping_add(self.x) as u16;
+                (addr, false)
+            }
+            AddrMode::Zpy => {
+                let addr = self.fetch_byte(memory).wrapping_add(self.y) as u16;
+                (addr, false)
+            }
+            AddrMode::Abs => {
+                let addr = self.addr_absolute(memory);
+                (addr, false)
+            }
+            AddrMode::Abx => {
+                let addr = self.addr_absolute(memory);
+                let addr_x = addr.wrapping_add(self.x as u16);
+                (addr_x, (addr ^ addr_x) >> 8 != 0)
+            }
+            AddrMode::Aby => {
+                let addr = self.addr_absolute(memory);
+                let addr_y = addr.wrapping_add(self.y as u16);
+                (addr_y, (addr ^ addr_y) >> 8 != 0)
+            }
+            AddrMode::Idx => {
+                let zero_page_addr_x = self.fetch_byte(memory).wrapping_add(self.x);
+                let effective_addr = CPU::<V>::read_word(memory, zero_page_addr_x as u16);
+                (effective_addr, false)
+            }
+            AddrMode::Idy => {
+                let zero_page_addr = self.fetch_byte(memory);
+                let effective_addr = CPU::<V>::read_word(memory, zero_page_addr as u16);
+                let effective_addr_y = effective_addr.wrapping_add(self.y as u16);
+                (effective_addr_y, (effective_addr ^ effective_addr_y) >> 8 != 0)
+            }
+            AddrMode::Izp => {
+                let zero_page_addr = self.fetch_byte(memory);
+                let effective_addr = CPU::<V>::read_word(memory, zero_page_addr as u16);
+                (effective_addr, false)
+            }
+            AddrMode::Ind => {
+                let ptr = self.addr_absolute(memory);
+                let addr = CPU::<V>::read_word(memory, ptr);
+                (addr, false)
+            }
+            AddrMode::Rel => {
+                let offset = self.fetch_byte(memory) as i8;
+                let addr = (self.pc as i32 + offset as i32) as u16;
+                (addr, (self.pc ^ addr) >> 8 != 0)
+            }
+            AddrMode::Imp | AddrMode::Acc => (0, false),
+        }
+    }
+
+    /// Run instructions until `cycles` is spent. Cycle accounting is driven
+    /// off each opcode's `Instr::cycles`, with the variant's page-cross
+    /// penalty layered on top for indexed reads; the only other debits are
+    /// `INTERRUPT_CYCLES` for each serviced `irq()`/`nmi()` and `BRA`'s
+    /// extra cycle for the taken branch (plus one more on a page cross).
+    /// An instruction is only fetched once its full
+    /// table cost fits in the remaining budget, so `execute` always stops
+    /// on a clean instruction boundary rather than running out of cycles
+    /// mid-decode.
+    pub fn execute<M: Bus>(&mut self, mut cycles: u32, memory: &mut M) {
+        loop {
+            if self.nmi_pending {
+                if cycles < INTERRUPT_CYCLES {
+                    break;
+                }
+                self.nmi_pending = false;
+                self.service_interrupt(memory, NMI_VECTOR, false);
+                cycles -= INTERRUPT_CYCLES;
+                self.total_cycles += INTERRUPT_CYCLES as u64;
+                continue;
+            }
+
+            if self.irq_pending && !self.ps.i() {
+                if cycles < INTERRUPT_CYCLES {
+                    break;
+                }
+                self.irq_pending = false;
+                self.service_interrupt(memory, IRQ_VECTOR, false);
+                cycles -= INTERRUPT_CYCLES;
+                self.total_cycles += INTERRUPT_CYCLES as u64;
+                continue;
+            }
+
+            if cycles == 0 {
+                break;
+            }
+
+            let opcode = memory.read(self.pc);
+            let instr = get_instruction::<V>(opcode);
+
+            if cycles < instr.cycles as u32 {
+                break;
+            }
+
+            if self.trace {
+                self.write_trace_line(memory);
+            }
+
+            self.pc += 1;
+            let (addr, page_crossed) = self.resolve_addr(instr.mode, memory);
+            let cross_penalty = V::indexed_cross_penalty();
+            let mut extra_cycles = 0u32;
+
+            match instr.op {
+                Op::Lda => {
+                    self.a = CPU::<V>::read_byte(memory, addr);
+                    self.set_zero_and_negative_flags(self.a);
+
+                    if page_crossed {
+                        extra_cycles += cross_penalty;
+                    }
+                }
+                Op::Jsr => {
+                    self.push_word(memory, self.pc - 1);
+                    self.pc = addr;
+                }
+                Op::Brk => {
+                    self.pc += 1; // skip the signature byte following BRK
+                    self.service_interrupt(memory, IRQ_VECTOR, true);
+                }
+                Op::Rti => {
+                    let raw = self.pull_byte(memory);
+                    let mut status = PS::from_bytes([raw]);
+                    status.set_b(false);
+                    status.set_u(1);
+                    self.ps = status;
+                    self.pc = self.pull_word(memory);
+                }
+                Op::Bra => {
+                    self.pc = addr;
+                    extra_cycles += 1; // always taken
+                    if page_crossed {
+                        extra_cycles += 1;
+                    }
+                }
+                Op::Stz => {
+                    memory.write(addr, 0);
+                }
+                Op::Phx => self.push_byte(memory, self.x),
+                Op::Phy => self.push_byte(memory, self.y),
+                Op::Plx => {
+                    self.x = self.pull_byte(memory);
+                    self.set_zero_and_negative_flags(self.x);
+                }
+                Op::Ply => {
+                    self.y = self.pull_byte(memory);
+                    self.set_zero_and_negative_flags(self.y);
+                }
+                Op::Tsb => {
+                    let value = CPU::<V>::read_byte(memory, addr);
+                    self.ps.set_z((self.a & value) == 0);
+                    memory.write(addr, value | self.a);
+                }
+                Op::Trb => {
+                    let value = CPU::<V>::read_byte(memory, addr);
+                    self.ps.set_z((self.a & value) == 0);
+                    memory.write(addr, value & !self.a);
+                }
+                Op::Inc => {
+                    if instr.mode == AddrMode::Acc {
+                        self.a = self.a.wrapping_add(1);
+                        self.set_zero_and_negative_flags(self.a);
+                    } else {
+                        let value = CPU::<V>::read_byte(memory, addr).wrapping_add(1);
+                        memory.write(addr, value);
+                        self.set_zero_and_negative_flags(value);
+                    }
+                }
+                Op::Dec => {
+                    if instr.mode == AddrMode::Acc {
+                        self.a = self.a.wrapping_sub(1);
+                        self.set_zero_and_negative_flags(self.a);
+                    } else {
+                        let value = CPU::<V>::read_byte(memory, addr).wrapping_sub(1);
+                        memory.write(addr, value);
+                        self.set_zero_and_negative_flags(value);
+                    }
+                }
+                Op::Bit => {
+                    let value = CPU::<V>::read_byte(memory, addr);
+                    self.ps.set_z((self.a & value) == 0);
+                }
+                Op::Adc => {
+                    let value = CPU::<V>::read_byte(memory, addr);
+                    self.adc(value);
+
+                    if page_crossed {
+                        extra_cycles += cross_penalty;
+                    }
+                }
+                Op::Sbc => {
+                    let value = CPU::<V>::read_byte(memory, addr);
+                    self.sbc(value);
+
+                    if page_crossed {
+                        extra_cycles += cross_penalty;
+                    }
+                }
+                Op::Xxx => print!("Instruction not handled {0}", opcode),
+            };
+
+            let total = instr.cycles as u32 + extra_cycles;
+            cycles = cycles.saturating_sub(total);
+            self.total_cycles += total as u64;
+        }
+    }
+
+    /// Format and emit one trace line for the instruction at `self.pc`,
+    /// without consuming any cycles or advancing `pc`.
+    fn write_trace_line<M: Bus>(&mut self, memory: &M) {
+        let pc = self.pc;
+        let opcode = memory.read(pc);
+        let instr = get_instruction::<V>(opcode);
+        let len = 1 + operand_len(instr.mode);
+
+        let mut bytes = [0u8; 3];
+        for (i, b) in bytes.iter_mut().enumerate().take(len as usize) {
+            *b = memory.read(pc + i as u16);
+        }
+
+        let decoded = disassemble::<V>(&bytes[..len as usize], pc);
+        let raw: String = bytes[..len as usize]
+            .iter()
+            .map(|b| format!("{:02X}", b))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        if let Some(writer) = self.trace_writer.as_mut() {
+            let _ = writeln!(
+                writer,
+                "{:04X}  {:<8} {:<14} A:{:02X} X:{:02X} Y:{:02X} P:{:02X} SP:{:02X} CYC:{}",
+                pc,
+                raw,
+                decoded.text,
+                self.a,
+                self.x,
+                self.y,
+                self.ps.into_bytes()[0],
+                self.sp,
+                self.total_cycles,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bus::RamBus;
+    use crate::variant::Cmos65C02;
+
+    #[test]
+    fn reset_loads_pc_from_reset_vector() {
+        let mut mem = RamBus::new();
+        mem.write(RESET_VECTOR, 0x00);
+        mem.write(RESET_VECTOR + 1, 0x80);
+
+        let mut cpu: CPU = CPU::new();
+        cpu.reset(&mem);
+
+        assert_eq!(cpu.pc, 0x8000);
+        assert_eq!(cpu.sp, 0xFE);
+    }
+
+    #[test]
+    fn push_and_pull_round_trip_through_the_stack_page() {
+        let mut mem = RamBus::new();
+        let mut cpu: CPU = CPU::new();
+        cpu.sp = 0xFF;
+
+        cpu.push_byte(&mut mem, 0x42);
+        assert_eq!(cpu.sp, 0xFE);
+        assert_eq!(mem.read(STACK_PAGE + 0xFF), 0x42);
+
+        let value = cpu.pull_byte(&mem);
+        assert_eq!(value, 0x42);
+        assert_eq!(cpu.sp, 0xFF);
+    }
+
+    #[test]
+    fn irq_pushes_pc_and_status_then_jumps_to_the_irq_vector() {
+        let mut mem = RamBus::new();
+        mem.write(RESET_VECTOR, 0x00);
+        mem.write(RESET_VECTOR + 1, 0x80);
+        mem.write(IRQ_VECTOR, 0x00);
+        mem.write(IRQ_VECTOR + 1, 0x90);
+
+        let mut cpu: CPU = CPU::new();
+        cpu.reset(&mem);
+        cpu.pc = 0x8000;
+        let sp_before = cpu.sp;
+
+        cpu.irq();
+        cpu.execute(7, &mut mem);
+
+        assert_eq!(cpu.pc, 0x9000);
+        assert!(cpu.ps.i());
+        assert_eq!(cpu.sp, sp_before.wrapping_sub(3));
+
+        let _status = cpu.pull_byte(&mem); // topmost: pushed status byte
+        assert_eq!(cpu.pull_word(&mem), 0x8000); // below it: the saved PC
+    }
+
+    #[test]
+    fn execute_charges_table_cycles_for_jsr_and_stops_on_a_boundary() {
+        let mut mem = RamBus::new();
+        mem.write(RESET_VECTOR, 0x00);
+        mem.write(RESET_VECTOR + 1, 0x80);
+        mem.write(0x8000, 0x20); // JSR $4242
+        mem.write(0x8001, 0x42);
+        mem.write(0x8002, 0x42);
+
+        let mut cpu: CPU = CPU::new();
+        cpu.reset(&mem);
+
+        // Exactly JSR's table cost: should execute it and stop cleanly
+        // instead of panicking while trying to decode the next instruction.
+        cpu.execute(6, &mut mem);
+
+        assert_eq!(cpu.pc, 0x4242);
+        assert_eq!(cpu.total_cycles, 6);
+    }
+
+    #[test]
+    fn execute_charges_table_cycles_for_brk() {
+        let mut mem = RamBus::new();
+        mem.write(RESET_VECTOR, 0x00);
+        mem.write(RESET_VECTOR + 1, 0x80);
+        mem.write(IRQ_VECTOR, 0x00);
+        mem.write(IRQ_VECTOR + 1, 0x90);
+        mem.write(0x8000, 0x00); // BRK
+
+        let mut cpu: CPU = CPU::new();
+        cpu.reset(&mem);
+
+        cpu.execute(7, &mut mem);
+
+        assert_eq!(cpu.pc, 0x9000);
+        assert_eq!(cpu.total_cycles, 7);
+    }
+
+    #[test]
+    fn trace_writer_formats_pc_bytes_mnemonic_and_register_snapshot() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        struct SharedBuf(Rc<RefCell<Vec<u8>>>);
+        impl Write for SharedBuf {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.borrow_mut().write(buf)
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut mem = RamBus::new();
+        mem.write(RESET_VECTOR, 0x00);
+        mem.write(RESET_VECTOR + 1, 0x80);
+        mem.write(0x8000, 0xA9); // LDA #$84
+        mem.write(0x8001, 0x84);
+
+        let out = Rc::new(RefCell::new(Vec::new()));
+        let mut cpu: CPU = CPU::new();
+        cpu.reset(&mem);
+        cpu.set_trace_writer(Box::new(SharedBuf(Rc::clone(&out))));
+
+        cpu.execute(2, &mut mem);
+
+        let line = String::from_utf8(out.borrow().clone()).unwrap();
+        assert_eq!(
+            line,
+            "8000  A9 84    LDA #$84       A:00 X:00 Y:00 P:00 SP:FE CYC:0\n"
+        );
+    }
+
+    #[test]
+    fn cmos_variant_runs_its_own_opcodes_and_still_charges_page_cross() {
+        let mut mem = RamBus::new();
+        mem.write(RESET_VECTOR, 0x00);
+        mem.write(RESET_VECTOR + 1, 0x80);
+        mem.write(0x8000, 0x80); // BRA +2
+        mem.write(0x8001, 0x02);
+        mem.write(0x8004, 0xB9); // LDA $40FF,Y
+        mem.write(0x8005, 0xFF);
+        mem.write(0x8006, 0x40);
+        mem.write(0x4100, 0x77); // $40FF + Y(1) crosses into $4100
+
+        let mut cpu: CPU<Cmos65C02> = CPU::new();
+        cpu.reset(&mem);
+        cpu.y = 1;
+
+        cpu.execute(3, &mut mem); // BRA: table cost 2 + 1 always-taken
+        assert_eq!(cpu.pc, 0x8004);
+        assert_eq!(cpu.total_cycles, 3);
+
+        cpu.execute(5, &mut mem); // LDA abs,Y: table cost 4 + 1 page-cross
+        assert_eq!(cpu.a, 0x77);
+        assert_eq!(cpu.total_cycles, 8);
+    }
+}
+
+#[cfg(all(test, feature = "decimal_mode"))]
+mod decimal_tests {
+    use super::*;
+    use crate::variant::Nmos6502;
+
+    #[test]
+    fn adc_decimal_wraps_with_carry() {
+        let mut cpu: CPU<Nmos6502> = CPU::new();
+        cpu.a = 0x99;
+        cpu.ps.set_d(true);
+        cpu.ps.set_c(false);
+
+        cpu.adc(0x01);
+
+        assert_eq!(cpu.a, 0x00);
+        assert!(cpu.ps.c());
+        // Z is derived from the binary intermediate ($99 + $01 = $9A),
+        // not the BCD-corrected result, matching NMOS hardware.
+        assert!(!cpu.ps.z());
+    }
+
+    #[test]
+    fn sbc_decimal_borrows() {
+        let mut cpu: CPU<Nmos6502> = CPU::new();
+        cpu.a = 0x00;
+        cpu.ps.set_d(true);
+        cpu.ps.set_c(true); // no borrow going in
+
+        cpu.sbc(0x01);
+
+        assert_eq!(cpu.a, 0x99);
+        assert!(!cpu.ps.c()); // borrow occurred
+    }
+}
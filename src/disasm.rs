@@ -0,0 +1,93 @@
+//! Disassembler: decodes a byte stream into 6502 mnemonic text.
+//!
+//! Built on top of the same [`Instr`] table the CPU decodes with, so the
+//! disassembly always matches what `CPU::execute` would actually do for a
+//! given [`Variant`](crate::variant::Variant).
+
+use crate::instr::{get_instruction, mnemonic, AddrMode};
+use crate::variant::Variant;
+
+/// One decoded instruction: its formatted text and how many bytes it
+/// occupied, so a caller can step `addr += len` to walk a region.
+pub struct Decoded {
+    pub text: String,
+    pub len: u16,
+}
+
+/// Decode the instruction at the start of `memory` into `Decoded` text,
+/// using `V`'s opcode table. `memory` is indexed locally, starting at its
+/// own byte 0 for the opcode -- it need not be (and for trace output,
+/// usually isn't) the full address space. `pc` is only the address that
+/// byte 0 corresponds to in that wider space, used to compute relative
+/// branch targets; pass 0 if the caller has no real address to rebase
+/// against.
+pub fn disassemble<V: Variant>(memory: &[u8], pc: u16) -> Decoded {
+    let opcode = byte(memory, 0);
+    let instr = get_instruction::<V>(opcode);
+    let mnem = mnemonic(instr.op);
+
+    let (operand, len) = match instr.mode {
+        AddrMode::Imm => (format!("#${:02X}", byte(memory, 1)), 2),
+        AddrMode::Zp0 => (format!("${:02X}", byte(memory, 1)), 2),
+        AddrMode::Zpx => (format!("${:02X},X", byte(memory, 1)), 2),
+        AddrMode::Zpy => (format!("${:02X},Y", byte(memory, 1)), 2),
+        AddrMode::Abs => (format!("${:04X}", word(memory, 1)), 3),
+        AddrMode::Abx => (format!("${:04X},X", word(memory, 1)), 3),
+        AddrMode::Aby => (format!("${:04X},Y", word(memory, 1)), 3),
+        AddrMode::Ind => (format!("(${:04X})", word(memory, 1)), 3),
+        AddrMode::Idx => (format!("(${:02X},X)", byte(memory, 1)), 2),
+        AddrMode::Idy => (format!("(${:02X}),Y", byte(memory, 1)), 2),
+        AddrMode::Izp => (format!("(${:02X})", byte(memory, 1)), 2),
+        AddrMode::Rel => {
+            let offset = byte(memory, 1) as i8;
+            let target = (pc as i32 + 2 + offset as i32) as u16;
+            (format!("${:04X}", target), 2)
+        }
+        AddrMode::Acc => ("A".to_string(), 1),
+        AddrMode::Imp => (String::new(), 1),
+    };
+
+    let text = if operand.is_empty() {
+        mnem.to_string()
+    } else {
+        format!("{} {}", mnem, operand)
+    };
+
+    Decoded { text, len }
+}
+
+/// Reads are saturating rather than panicking: `memory` is an arbitrary,
+/// possibly-truncated byte slice handed in by tooling (a trace buffer, a
+/// memory dump sliced at some boundary), not a guaranteed-complete
+/// instruction stream, so a multi-byte instruction that runs off the end
+/// gets a best-effort decode (missing bytes read as 0) instead of a panic.
+fn byte(memory: &[u8], offset: u16) -> u8 {
+    memory.get(offset as usize).copied().unwrap_or(0)
+}
+
+fn word(memory: &[u8], offset: u16) -> u16 {
+    byte(memory, offset) as u16 | ((byte(memory, offset + 1) as u16) << 8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variant::{Cmos65C02, Nmos6502};
+
+    #[test]
+    fn relative_branch_target_is_rebased_against_pc() {
+        // BRA +2 at $8000 should target $8004, not $0004.
+        let bytes = [0x80, 0x02];
+        let decoded = disassemble::<Cmos65C02>(&bytes, 0x8000);
+        assert_eq!(decoded.text, "BRA $8004");
+    }
+
+    #[test]
+    fn truncated_operand_decodes_best_effort_instead_of_panicking() {
+        // JSR as the last byte of a 3-byte slice: the opcode is there, but
+        // both operand bytes run off the end.
+        let bytes = [0x00, 0x00, 0x20];
+        let decoded = disassemble::<Nmos6502>(&bytes[2..], 0x8000);
+        assert_eq!(decoded.text, "JSR $0000");
+    }
+}
@@ -0,0 +1,231 @@
+//! The 256-entry opcode decode tables.
+//!
+//! Every opcode byte maps to exactly one `Instr` row describing how its
+//! operand is addressed, what operation it performs, and its base cycle
+//! count. `CPU::execute` looks the row up once per instruction instead of
+//! growing an ever-larger `match`. There is one table per chip [`Variant`](crate::variant::Variant):
+//! `NMOS_INSTRUCTIONS` for the classic 6502 and `CMOS_INSTRUCTIONS` for the
+//! 65C02, which layers its extra opcodes on top of the NMOS table.
+
+/// Addressing mode tag for an instruction's operand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddrMode {
+    Imm,
+    Zp0,
+    Zpx,
+    /// Zero-page indexed by Y. No opcode in either table uses this mode
+    /// yet (it would belong to `LDX zp,Y`, which isn't implemented), so
+    /// it's never constructed — kept as a placeholder for when that
+    /// opcode lands.
+    #[allow(dead_code)]
+    Zpy,
+    Abs,
+    Abx,
+    Aby,
+    Idx,
+    Idy,
+    Imp,
+    Acc,
+    Rel,
+    /// Indirect, `(abs)` — used only by `JMP (abs)`, which isn't
+    /// implemented yet, so this is never constructed. Kept as a
+    /// placeholder for when that opcode lands.
+    #[allow(dead_code)]
+    Ind,
+    /// 65C02 zero-page indirect, `(zp)` — like `Idy` but without the `Y`
+    /// index.
+    Izp,
+}
+
+/// Operation tag for an instruction, independent of addressing mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Lda,
+    Jsr,
+    Brk,
+    Rti,
+    /// 65C02: unconditional relative branch.
+    Bra,
+    /// 65C02: store zero.
+    Stz,
+    /// 65C02: push X.
+    Phx,
+    /// 65C02: push Y.
+    Phy,
+    /// 65C02: pull X.
+    Plx,
+    /// 65C02: pull Y.
+    Ply,
+    /// 65C02: test-and-reset bits against A.
+    Trb,
+    /// 65C02: test-and-set bits against A.
+    Tsb,
+    /// 65C02: increment (memory, or the accumulator in `Acc` mode).
+    Inc,
+    /// 65C02: decrement (memory, or the accumulator in `Acc` mode).
+    Dec,
+    /// 65C02: the immediate-mode `BIT` that only affects Z.
+    Bit,
+    Adc,
+    Sbc,
+    /// Placeholder for opcodes that have not been implemented yet.
+    Xxx,
+}
+
+/// A single decoded row of the opcode table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Instr {
+    pub opcode: u8,
+    pub mode: AddrMode,
+    pub op: Op,
+    pub cycles: u8,
+}
+
+const fn illegal(opcode: u8) -> Instr {
+    Instr {
+        opcode,
+        mode: AddrMode::Imp,
+        op: Op::Xxx,
+        cycles: 1,
+    }
+}
+
+const fn row(opcode: u8, mode: AddrMode, op: Op, cycles: u8) -> Instr {
+    Instr {
+        opcode,
+        mode,
+        op,
+        cycles,
+    }
+}
+
+const fn build_nmos_table() -> [Instr; 256] {
+    let mut table = [illegal(0); 256];
+
+    let mut opcode: usize = 0;
+    while opcode < 256 {
+        table[opcode] = illegal(opcode as u8);
+        opcode += 1;
+    }
+
+    table[0x20] = row(0x20, AddrMode::Abs, Op::Jsr, 6);
+    table[0x00] = row(0x00, AddrMode::Imp, Op::Brk, 7);
+    table[0x40] = row(0x40, AddrMode::Imp, Op::Rti, 6);
+
+    table[0xA9] = row(0xA9, AddrMode::Imm, Op::Lda, 2);
+    table[0xA5] = row(0xA5, AddrMode::Zp0, Op::Lda, 3);
+    table[0xB5] = row(0xB5, AddrMode::Zpx, Op::Lda, 4);
+    table[0xAD] = row(0xAD, AddrMode::Abs, Op::Lda, 4);
+    table[0xBD] = row(0xBD, AddrMode::Abx, Op::Lda, 4);
+    table[0xB9] = row(0xB9, AddrMode::Aby, Op::Lda, 4);
+    table[0xA1] = row(0xA1, AddrMode::Idx, Op::Lda, 6);
+    table[0xB1] = row(0xB1, AddrMode::Idy, Op::Lda, 5);
+
+    table[0x69] = row(0x69, AddrMode::Imm, Op::Adc, 2);
+    table[0x65] = row(0x65, AddrMode::Zp0, Op::Adc, 3);
+    table[0x75] = row(0x75, AddrMode::Zpx, Op::Adc, 4);
+    table[0x6D] = row(0x6D, AddrMode::Abs, Op::Adc, 4);
+    table[0x7D] = row(0x7D, AddrMode::Abx, Op::Adc, 4);
+    table[0x79] = row(0x79, AddrMode::Aby, Op::Adc, 4);
+    table[0x61] = row(0x61, AddrMode::Idx, Op::Adc, 6);
+    table[0x71] = row(0x71, AddrMode::Idy, Op::Adc, 5);
+
+    table[0xE9] = row(0xE9, AddrMode::Imm, Op::Sbc, 2);
+    table[0xE5] = row(0xE5, AddrMode::Zp0, Op::Sbc, 3);
+    table[0xF5] = row(0xF5, AddrMode::Zpx, Op::Sbc, 4);
+    table[0xED] = row(0xED, AddrMode::Abs, Op::Sbc, 4);
+    table[0xFD] = row(0xFD, AddrMode::Abx, Op::Sbc, 4);
+    table[0xF9] = row(0xF9, AddrMode::Aby, Op::Sbc, 4);
+    table[0xE1] = row(0xE1, AddrMode::Idx, Op::Sbc, 6);
+    table[0xF1] = row(0xF1, AddrMode::Idy, Op::Sbc, 5);
+
+    table
+}
+
+const fn build_cmos_table() -> [Instr; 256] {
+    let mut table = build_nmos_table();
+
+    table[0x80] = row(0x80, AddrMode::Rel, Op::Bra, 2);
+
+    table[0x64] = row(0x64, AddrMode::Zp0, Op::Stz, 3);
+    table[0x74] = row(0x74, AddrMode::Zpx, Op::Stz, 4);
+    table[0x9C] = row(0x9C, AddrMode::Abs, Op::Stz, 4);
+    table[0x9E] = row(0x9E, AddrMode::Abx, Op::Stz, 5);
+
+    table[0xDA] = row(0xDA, AddrMode::Imp, Op::Phx, 3);
+    table[0x5A] = row(0x5A, AddrMode::Imp, Op::Phy, 3);
+    table[0xFA] = row(0xFA, AddrMode::Imp, Op::Plx, 4);
+    table[0x7A] = row(0x7A, AddrMode::Imp, Op::Ply, 4);
+
+    table[0x14] = row(0x14, AddrMode::Zp0, Op::Trb, 5);
+    table[0x1C] = row(0x1C, AddrMode::Abs, Op::Trb, 6);
+    table[0x04] = row(0x04, AddrMode::Zp0, Op::Tsb, 5);
+    table[0x0C] = row(0x0C, AddrMode::Abs, Op::Tsb, 6);
+
+    table[0x1A] = row(0x1A, AddrMode::Acc, Op::Inc, 2);
+    table[0x3A] = row(0x3A, AddrMode::Acc, Op::Dec, 2);
+
+    table[0x89] = row(0x89, AddrMode::Imm, Op::Bit, 2);
+
+    table[0xB2] = row(0xB2, AddrMode::Izp, Op::Lda, 5);
+    table[0x72] = row(0x72, AddrMode::Izp, Op::Adc, 5);
+    table[0xF2] = row(0xF2, AddrMode::Izp, Op::Sbc, 5);
+
+    table
+}
+
+/// Decode table for the classic NMOS 6502.
+pub static NMOS_INSTRUCTIONS: [Instr; 256] = build_nmos_table();
+
+/// Decode table for the CMOS 65C02.
+pub static CMOS_INSTRUCTIONS: [Instr; 256] = build_cmos_table();
+
+/// Number of operand bytes `mode` consumes, not counting the opcode byte
+/// itself. Used by the disassembler and trace output to know how many
+/// bytes an instruction spans.
+pub fn operand_len(mode: AddrMode) -> u16 {
+    match mode {
+        AddrMode::Imp | AddrMode::Acc => 0,
+        AddrMode::Abs | AddrMode::Abx | AddrMode::Aby | AddrMode::Ind => 2,
+        AddrMode::Imm
+        | AddrMode::Zp0
+        | AddrMode::Zpx
+        | AddrMode::Zpy
+        | AddrMode::Idx
+        | AddrMode::Idy
+        | AddrMode::Izp
+        | AddrMode::Rel => 1,
+    }
+}
+
+/// Look up the decoded row for `opcode` in `V`'s table. Exposed so tooling
+/// (disassemblers, debuggers) can introspect the table without reaching
+/// into `CPU`.
+pub fn get_instruction<V: crate::variant::Variant>(opcode: u8) -> Instr {
+    V::table()[opcode as usize]
+}
+
+/// The mnemonic text for an operation, independent of addressing mode.
+/// Used by the disassembler and trace output.
+pub fn mnemonic(op: Op) -> &'static str {
+    match op {
+        Op::Lda => "LDA",
+        Op::Jsr => "JSR",
+        Op::Brk => "BRK",
+        Op::Rti => "RTI",
+        Op::Bra => "BRA",
+        Op::Stz => "STZ",
+        Op::Phx => "PHX",
+        Op::Phy => "PHY",
+        Op::Plx => "PLX",
+        Op::Ply => "PLY",
+        Op::Trb => "TRB",
+        Op::Tsb => "TSB",
+        Op::Inc => "INC",
+        Op::Dec => "DEC",
+        Op::Bit => "BIT",
+        Op::Adc => "ADC",
+        Op::Sbc => "SBC",
+        Op::Xxx => "???",
+    }
+}
@@ -1,199 +1,112 @@
-use std::{u8, usize};
-
-use modular_bitfield::prelude::*;
-
-#[bitfield(bits = 8)]
-struct PS {
-    c: bool, //Carry Flag
-    z: bool, //Zero Flag
-    i: bool, //Interupt Disable
-    d: bool, //Decimal Mode
-    b: bool, //Break Command
-    u: B1,   //Unused
-    v: bool, //Overflow Flag
-    n: bool, //Negative Flag
-}
-
-struct MEM {
-    data: [u8; 65536]
-}
+mod bus;
+mod cpu;
+mod disasm;
+mod instr;
+mod variant;
 
-impl MEM {
-    fn initialize(&mut self) {
-        self.data = [0; 65536];
-    }
-
-    fn write_word(&mut self, value: &u16, addr: &u16, cycles: &mut u32) {
-        self.data[*addr as usize] = *value as u8;
-        self.data[*addr as usize + 1] = (*value >> 8) as u8;
-        *cycles -= 2;
-    }
-}
-
-struct CPU {
-    pc: u16, //Program Counter
-    sp: u8,  //Stack Poniter
-    a: u8,   //Accumulator
-    x: u8,   //Register X
-    y: u8,   //Register Y
-    ps: PS,  //Processor Status
-}
-
-impl CPU {
-    // Upcodes
-    const INS_LDA_IM: u8 = 0xA9;
-    const INS_LDA_ZP: u8 = 0xA5;
-    const INS_LDA_ZX: u8 = 0xB5;
-    const INS_LDA_AB: u8 = 0xAD;
-    const INS_LDA_AX: u8 = 0xBD;
-    const INS_LDA_AY: u8 = 0xB9;
-    const INS_LDA_IX: u8 = 0xA1;
-    const INS_LDA_IY: u8 = 0xB1;
-    const INS_JSR: u8 = 0x20;
-
-    fn reset(&mut self, mem: &mut MEM){
-        self.pc = 0xFFFC;
-        self.sp = 0xFE;
-        self.ps = PS::new();
-        self.a = 0;
-        self.x = 0;
-        self.y = 0;
-        mem.initialize();
-    }
-
-    fn fetch_byte(&mut self, cycles: &mut u32, memory: &MEM) -> u8 {
-        let data = memory.data[self.pc as usize];
-        
-        self.pc += 1;
-        *cycles -= 1;
-        return data;
-    }
+use bus::{Bus, RamBus};
+use cpu::CPU;
+use disasm::disassemble;
+use variant::Cmos65C02;
 
-    fn fetch_word(&mut self, cycles: &mut u32, memory: &MEM) -> u16 {
-        // 6502 is little endian
-        let mut data = memory.data[self.pc as usize] as u16;        
-        self.pc += 1;
-
-        data |= (memory.data[self.pc as usize] as u16) << 8;        
-        self.pc += 1;
-        
-        *cycles -= 2;
+fn main() {
+    let mut mem = RamBus::new();
 
-        return data;
-    }
+    // Reset vector points at the start of the program.
+    mem.write(0xFFFC, 0x00);
+    mem.write(0xFFFD, 0x80);
 
-    fn read_byte(cycles: &mut u32, memory: &MEM, addr: u16) -> u8 {
-        *cycles -= 1;
-        memory.data[addr as usize]
-    }
+    mem.write(0x8000, 0x20); // JSR $4242
+    mem.write(0x8001, 0x42);
+    mem.write(0x8002, 0x42);
+    mem.write(0x4242, 0xA9); // LDA #$84
+    mem.write(0x4243, 0x84);
 
-    fn read_word(cycles: &mut u32, memory: &MEM, addr: u16) -> u16 {
-        *cycles -= 1;
-        let low_byte = memory.data[addr as usize];
-        let hi_byte = memory.data[(addr + 1) as usize];
+    let mut cpu: CPU = CPU::new();
 
-        (low_byte | (hi_byte << 8)) as u16
-    }
+    cpu.reset(&mem);
 
-    fn addr_absolute(&mut self, cycles: &mut u32, memory: &MEM) -> u16 {
-        self.fetch_word(cycles, memory)
-    }
+    cpu.execute(8, &mut mem);
 
-    fn set_zero_and_negative_flags(&mut self, register: u8) {
-        self.ps.set_z(register == 0);
-        self.ps.set_n((register & 0b1000000) > 0);
-    }
+    println!(
+        "status: c={} z={} i={} d={} b={} u={} v={} n={}",
+        cpu.ps.c(),
+        cpu.ps.z(),
+        cpu.ps.i(),
+        cpu.ps.d(),
+        cpu.ps.b(),
+        cpu.ps.u(),
+        cpu.ps.v(),
+        cpu.ps.n()
+    );
+
+    // Wipe the bus clean so it can be handed off for reuse.
+    mem.initialize();
+    println!("after initialize, byte at $8000 = {:02X}", mem.read(0x8000));
+
+    cmos_demo();
+}
 
-    fn execute(&mut self, mut cycles: u32, memory: &mut MEM) {
-        while cycles > 0 {
-            let instruction = self.fetch_byte(&mut cycles, memory);
-
-            match instruction {
-                CPU::INS_LDA_IM => {
-                    let value: u8 = self.fetch_byte(&mut cycles, memory);
-                    self.a = value;
-                    self.set_zero_and_negative_flags(self.a);
-                },
-                CPU::INS_LDA_ZP => {
-                    let zero_page_addr = self.fetch_byte(&mut cycles, memory) as u16;
-                    self.a = CPU::read_byte(&mut cycles, memory, zero_page_addr);                    
-                    self.set_zero_and_negative_flags(self.a);
-                },
-                CPU::INS_LDA_ZX => {
-                    let zero_page_addr = (self.fetch_byte(&mut cycles, memory) + self.x) as u16;
-                    cycles -= 1;
-                    self.a = CPU::read_byte(&mut cycles, memory, zero_page_addr);
-                },
-                CPU::INS_LDA_AB => {
-                    let addr = self.addr_absolute(&mut cycles, memory);
-                    self.a = CPU::read_byte(&mut cycles, memory, addr);
-                },
-                CPU::INS_LDA_AX => {
-                    let addr = self.addr_absolute(&mut cycles, memory);
-                    let addr_x = addr + self.x as u16;
-                    self.a = CPU::read_byte(&mut cycles, memory, addr_x);
-                },
-                CPU::INS_LDA_AY => {
-                    let addr = self.addr_absolute(&mut cycles, memory);
-                    let addr_y = addr + self.y as u16;
-                    
-                    if (addr ^ addr_y) >> 8 == 0 {
-                        cycles -= 1;
-                    }
-
-                    self.a = CPU::read_byte(&mut cycles, memory, addr_y);
-                },
-                CPU::INS_LDA_IX => {
-                    let zero_page_addr_x: u8 = self.fetch_byte(&mut cycles, memory) + self.x;
-                    cycles -= 1;
-                    let effective_addr: u16 = CPU::read_word(&mut cycles, memory, zero_page_addr_x as u16);
-                    self.a = CPU::read_byte(&mut cycles, memory, effective_addr);
-                },
-                CPU::INS_LDA_IY => {
-                    let zero_page_addr: u8 = self.fetch_byte(&mut cycles, memory);
-                    let effective_addr: u16 = CPU::read_word(&mut cycles, memory, zero_page_addr as u16);
-                    let effective_addr_y: u16 = effective_addr + self.y as u16;
-                    
-                    if (effective_addr ^ effective_addr_y) >> 8 == 0{
-                        cycles -= 1;
-                    }
-
-                    self.a = CPU::read_byte(&mut cycles, memory, effective_addr_y);
-                },
-                CPU::INS_JSR => {
-                    let sub_addr = self.fetch_word(&mut cycles, memory);
-                    memory.write_word(&(self.pc - 1),&(self.sp as u16), &mut cycles);
-                    self.pc = sub_addr;
-                    self.sp += 1;
-                    cycles -= 1;
-                },
-                _ => print!("Instruction not handled {0}", instruction),
-            };
-        }
+/// A second demo program, run on the 65C02 variant, that exercises what
+/// the NMOS run above never touches: the 65C02-only opcodes, trace
+/// output, interrupt delivery, and stepping a byte stream with the
+/// disassembler the way a debugger would.
+fn cmos_demo() {
+    let mut mem = RamBus::new();
+
+    // A toy memory-mapped output port at $10: writes are forwarded to the
+    // handler instead of landing in RAM, so the STZ/TSB pair below never
+    // actually sees its own write reflected back on the next read.
+    mem.register_write_handler(0x10, 0x10, |_, val| {
+        println!("port $10 <- {:02X}", val);
+    });
+
+    mem.write(0xFFFC, 0x00);
+    mem.write(0xFFFD, 0x80); // reset -> $8000
+    mem.write(0xFFFA, 0x10);
+    mem.write(0xFFFB, 0x90); // NMI vector -> $9010
+    mem.write(0xFFFE, 0x00);
+    mem.write(0xFFFF, 0x90); // IRQ vector -> $9000
+    mem.write(0x9000, 0x40); // RTI, resumes after the IRQ
+    mem.write(0x9010, 0x40); // RTI, resumes after the NMI
+
+    mem.write(0x8000, 0xDA); // PHX
+    mem.write(0x8001, 0x5A); // PHY
+    mem.write(0x8002, 0x64); // STZ $10
+    mem.write(0x8003, 0x10);
+    mem.write(0x8004, 0x04); // TSB $10
+    mem.write(0x8005, 0x10);
+    mem.write(0x8006, 0x1A); // INC A
+    mem.write(0x8007, 0x3A); // DEC A
+    mem.write(0x8008, 0x89); // BIT #$FF
+    mem.write(0x8009, 0xFF);
+    mem.write(0x800A, 0x80); // BRA +2, skips the TRB below
+    mem.write(0x800B, 0x02);
+    mem.write(0x800C, 0x14); // TRB $10 (not reached)
+    mem.write(0x800D, 0x10);
+    mem.write(0x800E, 0xFA); // PLX
+    mem.write(0x800F, 0x7A); // PLY
+
+    let mut cpu: CPU<Cmos65C02> = CPU::new();
+    cpu.set_trace_writer(Box::new(std::io::stdout()));
+    cpu.reset(&mem);
+
+    // Both land before the first instruction fetch and round-trip through
+    // an RTI before the PHX/PHY/... program actually starts.
+    cpu.nmi();
+    cpu.irq();
+
+    cpu.execute(57, &mut mem);
+
+    // Walk the same bytes with the disassembler directly, stepping by the
+    // length each decoded instruction reports.
+    let program = [0xDAu8, 0x5A, 0x64, 0x10, 0x04, 0x10];
+    let mut addr = 0x8000u16;
+    let mut offset = 0usize;
+    while offset < program.len() {
+        let decoded = disassemble::<Cmos65C02>(&program[offset..], addr);
+        println!("{:04X}  {}", addr, decoded.text);
+        offset += decoded.len as usize;
+        addr += decoded.len;
     }
 }
-
-fn main() {
-    let mut mem: MEM = MEM {
-        data: [0; 65536],
-    };
-    
-    let mut cpu: CPU = CPU {
-        pc: 0,
-        sp: 0,
-        a: 0,
-        x: 0,
-        y: 0,
-        ps: PS::new(),
-    };
-
-    cpu.reset(&mut mem);
-
-    mem.data[0xFFFC] = CPU::INS_JSR;
-    mem.data[0xFFFD] = 0x42;
-    mem.data[0xFFFE] = 0x42;
-    mem.data[0x4242] = CPU::INS_LDA_IM;
-    mem.data[0x4243] = 0x84;
-    
-    cpu.execute(8, &mut mem);
-}
\ No newline at end of file
@@ -0,0 +1,51 @@
+//! Chip variant selection.
+//!
+//! The core is generic over a [`Variant`], which supplies the decode table
+//! and the handful of behavioral differences between the classic NMOS 6502
+//! and the CMOS 65C02. A consumer picks the chip at construction time via
+//! `CPU::<Variant>::new()`.
+
+use crate::instr::{Instr, CMOS_INSTRUCTIONS, NMOS_INSTRUCTIONS};
+
+pub trait Variant {
+    fn table() -> &'static [Instr; 256];
+
+    /// Whether `BRK` clears the D flag on entry (a 65C02 fix; NMOS leaves it
+    /// untouched).
+    fn clears_decimal_on_brk() -> bool {
+        false
+    }
+
+    /// Extra cycles charged when an indexed read crosses a page boundary.
+    fn indexed_cross_penalty() -> u32 {
+        1
+    }
+}
+
+/// The classic NMOS 6502.
+pub struct Nmos6502;
+
+impl Variant for Nmos6502 {
+    fn table() -> &'static [Instr; 256] {
+        &NMOS_INSTRUCTIONS
+    }
+}
+
+/// The CMOS 65C02.
+pub struct Cmos65C02;
+
+impl Variant for Cmos65C02 {
+    fn table() -> &'static [Instr; 256] {
+        &CMOS_INSTRUCTIONS
+    }
+
+    fn clears_decimal_on_brk() -> bool {
+        true
+    }
+
+    // Real WDC 65C02 hardware still charges the page-cross penalty for
+    // indexed reads (LDA/ADC/SBC abs,X/Y and (zp),Y) -- the chip's actual
+    // cycle-count fixes are narrower (certain RMW ops, the JMP (abs,X)
+    // bug) and don't remove this one, so this falls back to the default
+    // (same as NMOS) rather than overriding it to 0.
+}